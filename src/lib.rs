@@ -37,6 +37,7 @@
 //! ```
 
 use image::{DynamicImage, Rgba, RgbaImage};
+use rayon::prelude::*;
 
 /// Configuration options for SVG conversion
 #[derive(Debug, Clone)]
@@ -49,6 +50,31 @@ pub struct ConversionOptions {
     pub skip_transparent: bool,
     /// Enable SVG shape-rendering="crispEdges" for pixel-perfect rendering
     pub crisp_edges: bool,
+    /// Reduce the image to at most this many colors via median-cut quantization
+    /// before rectangle extraction, collapsing noisy or anti-aliased pixel art
+    /// into far fewer rectangles. `None` disables quantization.
+    pub max_colors: Option<usize>,
+    /// How rectangles are encoded in the generated SVG
+    pub output: OutputStyle,
+    /// Rectangle extraction strategy: trade CPU time for fewer, larger rectangles
+    pub quality: ExtractionQuality,
+    /// Split the image into this many horizontal bands and extract each in
+    /// parallel with rayon, merging rectangles across band boundaries
+    /// afterward. `None` (the default) extracts single-threaded.
+    pub parallel_tiles: Option<usize>,
+    /// Solid color drawn as a full-canvas rectangle behind the art, useful
+    /// when flattening transparency. `None` leaves the background transparent.
+    pub background: Option<Color>,
+    /// Emit a `viewBox` so the SVG scales responsively independent of its
+    /// `width`/`height` attributes. Implied when `output_width` or
+    /// `output_height` is set.
+    pub viewbox: bool,
+    /// Explicit output width, overriding the scale-derived size (the
+    /// viewBox maps the original content to this width)
+    pub output_width: Option<u32>,
+    /// Explicit output height, overriding the scale-derived size (the
+    /// viewBox maps the original content to this height)
+    pub output_height: Option<u32>,
 }
 
 impl Default for ConversionOptions {
@@ -58,10 +84,41 @@ impl Default for ConversionOptions {
             alpha_threshold: 1,
             skip_transparent: true,
             crisp_edges: true,
+            max_colors: None,
+            output: OutputStyle::default(),
+            quality: ExtractionQuality::default(),
+            parallel_tiles: None,
+            background: None,
+            viewbox: false,
+            output_width: None,
+            output_height: None,
         }
     }
 }
 
+/// Rectangle extraction strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtractionQuality {
+    /// Greedy max-width-then-height extraction (fast, more rectangles)
+    #[default]
+    Fast,
+    /// Histogram-based maximal-rectangle search across the whole grid on
+    /// every pick (slower, fewer and larger rectangles)
+    Best,
+}
+
+/// How rectangles are encoded in the generated SVG
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputStyle {
+    /// One `<rect>` element per rectangle (default, maximally compatible)
+    #[default]
+    Rects,
+    /// Rectangles are grouped by color and each group is encoded as a single
+    /// `<path>` with one subpath per rectangle, shrinking output size when
+    /// many rectangles share a color
+    Paths,
+}
+
 /// Represents a color with RGBA components
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color {
@@ -145,6 +202,150 @@ impl Rectangle {
     }
 }
 
+/// An axis-aligned box of RGB space holding the colors it currently contains,
+/// used by [`quantize_colors`] to perform median-cut color quantization.
+struct ColorBox {
+    /// Distinct RGB colors in this box, paired with how many pixels had that color
+    colors: Vec<([u8; 3], u64)>,
+}
+
+impl ColorBox {
+    fn min_max(&self) -> ([u8; 3], [u8; 3]) {
+        let mut min = [u8::MAX; 3];
+        let mut max = [u8::MIN; 3];
+        for (rgb, _) in &self.colors {
+            for c in 0..3 {
+                min[c] = min[c].min(rgb[c]);
+                max[c] = max[c].max(rgb[c]);
+            }
+        }
+        (min, max)
+    }
+
+    /// Channel (0=R, 1=G, 2=B) with the greatest spread, used to pick the split axis
+    fn widest_channel(&self) -> usize {
+        let (min, max) = self.min_max();
+        let spreads = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+        if spreads[0] >= spreads[1] && spreads[0] >= spreads[2] {
+            0
+        } else if spreads[1] >= spreads[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Per-channel average color of all pixels in this box, weighted by pixel count
+    fn average_color(&self) -> [u8; 3] {
+        let mut sum = [0u64; 3];
+        let mut count = 0u64;
+        for (rgb, n) in &self.colors {
+            for c in 0..3 {
+                sum[c] += rgb[c] as u64 * n;
+            }
+            count += n;
+        }
+        if count == 0 {
+            return [0, 0, 0];
+        }
+        [
+            (sum[0] / count) as u8,
+            (sum[1] / count) as u8,
+            (sum[2] / count) as u8,
+        ]
+    }
+
+    /// Split this box into two at the median of its widest channel
+    fn split(mut self) -> (ColorBox, ColorBox) {
+        let channel = self.widest_channel();
+        self.colors.sort_by_key(|(rgb, _)| rgb[channel]);
+
+        let total: u64 = self.colors.iter().map(|(_, n)| n).sum();
+        let mut running = 0u64;
+        let mut split_at = self.colors.len() / 2;
+        for (i, (_, n)) in self.colors.iter().enumerate() {
+            running += n;
+            if running >= total / 2 {
+                split_at = i + 1;
+                break;
+            }
+        }
+        let split_at = split_at.clamp(1, self.colors.len().saturating_sub(1).max(1));
+
+        let right = self.colors.split_off(split_at);
+        (ColorBox { colors: self.colors }, ColorBox { colors: right })
+    }
+}
+
+/// Reduce `image` to at most `max_colors` distinct RGB colors using median-cut
+/// quantization, returning a new image with every pixel remapped to its box's
+/// representative color. Alpha is preserved as-is; pixels below
+/// `alpha_threshold` are ignored when building the palette.
+fn quantize_colors(image: &RgbaImage, alpha_threshold: u8, max_colors: usize) -> RgbaImage {
+    let max_colors = max_colors.max(1);
+
+    let mut histogram: std::collections::HashMap<[u8; 3], u64> = std::collections::HashMap::new();
+    for pixel in image.pixels() {
+        if pixel[3] < alpha_threshold {
+            continue;
+        }
+        *histogram.entry([pixel[0], pixel[1], pixel[2]]).or_insert(0) += 1;
+    }
+
+    if histogram.is_empty() || histogram.len() <= max_colors {
+        return image.clone();
+    }
+
+    let mut boxes = vec![ColorBox {
+        colors: histogram.into_iter().collect(),
+    }];
+
+    while boxes.len() < max_colors {
+        let Some((idx, _)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.colors.len() > 1)
+            .max_by_key(|(_, b)| {
+                let (min, max) = b.min_max();
+                (0..3)
+                    .map(|c| (max[c] - min[c]) as u32)
+                    .max()
+                    .unwrap_or(0)
+            })
+        else {
+            break;
+        };
+
+        let splitting = boxes.swap_remove(idx);
+        let (left, right) = splitting.split();
+        boxes.push(left);
+        boxes.push(right);
+    }
+
+    let mut palette: std::collections::HashMap<[u8; 3], [u8; 3]> =
+        std::collections::HashMap::new();
+    for b in &boxes {
+        let representative = b.average_color();
+        for (rgb, _) in &b.colors {
+            palette.insert(*rgb, representative);
+        }
+    }
+
+    let mut quantized = image.clone();
+    for pixel in quantized.pixels_mut() {
+        if pixel[3] < alpha_threshold {
+            continue;
+        }
+        if let Some(representative) = palette.get(&[pixel[0], pixel[1], pixel[2]]) {
+            pixel[0] = representative[0];
+            pixel[1] = representative[1];
+            pixel[2] = representative[2];
+        }
+    }
+
+    quantized
+}
+
 /// Internal image processor for rectangle extraction
 struct ImageProcessor {
     image: RgbaImage,
@@ -263,6 +464,172 @@ impl ImageProcessor {
 
         rectangles
     }
+
+    /// Find the largest axis-aligned rectangle of `color` anywhere in the
+    /// unprocessed grid, using a histogram-based largest-rectangle search:
+    /// `heights[col]` tracks consecutive unprocessed rows matching `color`
+    /// ending at the current row, and the classic monotonic-stack algorithm
+    /// finds the max-area rectangle in that histogram at every row.
+    fn find_best_rectangle(&self, color: Color) -> Option<Rectangle> {
+        let mut heights = vec![0u32; self.width as usize];
+        let mut best: Option<Rectangle> = None;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let matches = !self.is_processed(x, y) && self.get_pixel_color(x, y) == Some(color);
+                heights[x as usize] = if matches { heights[x as usize] + 1 } else { 0 };
+            }
+
+            if let Some((start_col, width, height)) = largest_rectangle_in_histogram(&heights) {
+                let area = width as u64 * height as u64;
+                if best.as_ref().is_none_or(|b| area > b.area()) {
+                    best = Some(Rectangle::new(
+                        start_col as u32,
+                        y + 1 - height,
+                        width,
+                        height,
+                        color,
+                    ));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Extract rectangles via repeated global maximal-rectangle search
+    /// instead of the greedy row-first scan, yielding fewer and larger
+    /// rectangles at the cost of rescanning the whole grid for every pick
+    fn extract_rectangles_optimal(&mut self) -> Vec<Rectangle> {
+        let mut rectangles = Vec::new();
+
+        loop {
+            let mut colors: std::collections::HashSet<Color> = std::collections::HashSet::new();
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    if !self.is_processed(x, y) {
+                        if let Some(color) = self.get_pixel_color(x, y) {
+                            colors.insert(color);
+                        } else {
+                            self.processed[y as usize][x as usize] = true;
+                        }
+                    }
+                }
+            }
+
+            if colors.is_empty() {
+                break;
+            }
+
+            let best = colors
+                .into_iter()
+                .filter_map(|color| self.find_best_rectangle(color))
+                .max_by_key(|rect| rect.area());
+
+            let Some(rect) = best else { break };
+            self.mark_processed(&rect);
+            rectangles.push(rect);
+        }
+
+        rectangles
+    }
+}
+
+/// Find the maximum-area rectangle in a histogram using a monotonic stack of
+/// increasing bar indices; when a shorter bar appears, pop and compute
+/// `height * width` where width spans from the previous stack element to the
+/// current index. Returns `(start_column, width, height)` of the best
+/// rectangle found, or `None` if every bar has zero height.
+fn largest_rectangle_in_histogram(heights: &[u32]) -> Option<(usize, u32, u32)> {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut best: Option<(usize, u32, u32)> = None;
+
+    for i in 0..=heights.len() {
+        let h = heights.get(i).copied().unwrap_or(0);
+
+        while let Some(&top) = stack.last() {
+            if heights[top] <= h {
+                break;
+            }
+            stack.pop();
+
+            let height = heights[top];
+            let start = stack.last().map_or(0, |&s| s + 1);
+            let width = (i - start) as u32;
+            let area = width as u64 * height as u64;
+
+            if best.as_ref().is_none_or(|&(_, w, ht)| area > w as u64 * ht as u64) {
+                best = Some((start, width, height));
+            }
+        }
+
+        stack.push(i);
+    }
+
+    best.filter(|&(_, _, height)| height > 0)
+}
+
+/// Extract rectangles by splitting the image into `tiles` horizontal bands
+/// and running extraction on each band independently (and in parallel, via
+/// rayon), then fusing rectangles across band boundaries that share the same
+/// `x`, `width`, and `Color` and are vertically contiguous. Bands are
+/// stitched back together in row order, so results are deterministic
+/// regardless of which band finishes first.
+fn extract_rectangles_tiled(
+    image: &RgbaImage,
+    alpha_threshold: u8,
+    quality: ExtractionQuality,
+    tiles: usize,
+) -> Vec<Rectangle> {
+    let (width, height) = image.dimensions();
+    let tiles = tiles.max(1).min(height.max(1) as usize);
+    let band_height = height.div_ceil(tiles as u32);
+
+    let bands: Vec<(u32, u32)> = (0..tiles as u32)
+        .map(|i| {
+            let start = i * band_height;
+            let end = (start + band_height).min(height);
+            (start, end)
+        })
+        .filter(|&(start, end)| start < end)
+        .collect();
+
+    let mut band_results: Vec<(u32, Vec<Rectangle>)> = bands
+        .into_par_iter()
+        .map(|(start, end)| {
+            let band = image::imageops::crop_imm(image, 0, start, width, end - start).to_image();
+            let mut processor = ImageProcessor::new(DynamicImage::ImageRgba8(band), alpha_threshold);
+            let mut rects = match quality {
+                ExtractionQuality::Fast => processor.extract_rectangles(),
+                ExtractionQuality::Best => processor.extract_rectangles_optimal(),
+            };
+            for rect in &mut rects {
+                rect.y += start;
+            }
+            (start, rects)
+        })
+        .collect();
+
+    band_results.sort_by_key(|&(start, _)| start);
+
+    let mut merged: Vec<Rectangle> = Vec::new();
+    for (_, rects) in band_results {
+        for rect in rects {
+            let fuse_with = merged.iter().position(|r| {
+                r.color == rect.color
+                    && r.x == rect.x
+                    && r.width == rect.width
+                    && r.y + r.height == rect.y
+            });
+
+            match fuse_with {
+                Some(i) => merged[i].height += rect.height,
+                None => merged.push(rect),
+            }
+        }
+    }
+
+    merged
 }
 
 /// Conversion result containing SVG content and statistics
@@ -315,9 +682,27 @@ pub fn convert_image_to_svg(
     let width = image.width();
     let height = image.height();
 
+    // Optionally collapse the palette before extraction to shrink output size
+    let source = if let Some(max_colors) = options.max_colors {
+        DynamicImage::ImageRgba8(quantize_colors(
+            &image.to_rgba8(),
+            options.alpha_threshold,
+            max_colors,
+        ))
+    } else {
+        image.clone()
+    };
+
     // Process image and extract rectangles
-    let mut processor = ImageProcessor::new(image.clone(), options.alpha_threshold);
-    let rectangles = processor.extract_rectangles();
+    let rectangles = if let Some(tiles) = options.parallel_tiles {
+        extract_rectangles_tiled(&source.to_rgba8(), options.alpha_threshold, options.quality, tiles)
+    } else {
+        let mut processor = ImageProcessor::new(source, options.alpha_threshold);
+        match options.quality {
+            ExtractionQuality::Fast => processor.extract_rectangles(),
+            ExtractionQuality::Best => processor.extract_rectangles_optimal(),
+        }
+    };
 
     // Generate SVG content
     let svg_content = create_svg(&rectangles, width, height, &options);
@@ -329,6 +714,45 @@ pub fn convert_image_to_svg(
     })
 }
 
+/// Group rectangles by color and encode each group as a single `<path>`,
+/// with one rectangle subpath per `M x y h w v h h-w z` run. This avoids
+/// repeating the `fill` attribute for every rectangle that shares a color.
+fn grouped_paths(rectangles: &[Rectangle], scale: u32) -> Vec<String> {
+    let mut order: Vec<Color> = Vec::new();
+    let mut groups: std::collections::HashMap<Color, Vec<&Rectangle>> =
+        std::collections::HashMap::new();
+
+    for rect in rectangles {
+        groups.entry(rect.color).or_insert_with(|| {
+            order.push(rect.color);
+            Vec::new()
+        });
+        groups.get_mut(&rect.color).unwrap().push(rect);
+    }
+
+    order
+        .into_iter()
+        .map(|color| {
+            let rects = &groups[&color];
+            let mut d = String::new();
+            for rect in rects {
+                let x = rect.x * scale;
+                let y = rect.y * scale;
+                let w = rect.width * scale;
+                let h = rect.height * scale;
+                d.push_str(&format!("M{} {}h{}v{}h-{}z", x, y, w, h, w));
+            }
+
+            let mut path = format!(r##"<path fill="#{}" "##, color.to_hex());
+            if color.a != 255 {
+                path.push_str(&format!(r#"opacity="{:.3}" "#, color.opacity()));
+            }
+            path.push_str(&format!(r#"d="{}"/>"#, d));
+            path
+        })
+        .collect()
+}
+
 /// Create SVG content from rectangles
 fn create_svg(
     rectangles: &[Rectangle],
@@ -342,12 +766,39 @@ fn create_svg(
     svg.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
     svg.push('\n');
 
+    let intrinsic_width = width * options.scale;
+    let intrinsic_height = height * options.scale;
+
+    let (doc_width, doc_height) = match (options.output_width, options.output_height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (
+            w,
+            (intrinsic_height as u64 * w as u64 / intrinsic_width.max(1) as u64) as u32,
+        ),
+        (None, Some(h)) => (
+            (intrinsic_width as u64 * h as u64 / intrinsic_height.max(1) as u64) as u32,
+            h,
+        ),
+        (None, None) => (intrinsic_width, intrinsic_height),
+    };
+
+    let emit_viewbox =
+        options.viewbox || options.output_width.is_some() || options.output_height.is_some();
+
     let mut svg_tag = format!(
-        r#"<svg version="1.1" width="{}" height="{}" xmlns="http://www.w3.org/2000/svg""#,
-        width * options.scale,
-        height * options.scale
+        r#"<svg version="1.1" width="{}" height="{}""#,
+        doc_width, doc_height
     );
 
+    if emit_viewbox {
+        svg_tag.push_str(&format!(
+            r#" viewBox="0 0 {} {}""#,
+            intrinsic_width, intrinsic_height
+        ));
+    }
+
+    svg_tag.push_str(r#" xmlns="http://www.w3.org/2000/svg""#);
+
     if options.crisp_edges {
         svg_tag.push_str(r#" shape-rendering="crispEdges""#);
     }
@@ -356,12 +807,29 @@ fn create_svg(
     svg.push_str(&svg_tag);
     svg.push('\n');
 
-    // Add rectangles
-    for rect in rectangles {
-        svg.push_str(&rect.to_svg(options.scale));
+    // Solid background drawn behind the art, e.g. to flatten transparency
+    if let Some(background) = options.background {
+        let background_rect = Rectangle::new(0, 0, width, height, background);
+        svg.push_str(&background_rect.to_svg(options.scale));
         svg.push('\n');
     }
 
+    // Add rectangles
+    match options.output {
+        OutputStyle::Rects => {
+            for rect in rectangles {
+                svg.push_str(&rect.to_svg(options.scale));
+                svg.push('\n');
+            }
+        }
+        OutputStyle::Paths => {
+            for path in grouped_paths(rectangles, options.scale) {
+                svg.push_str(&path);
+                svg.push('\n');
+            }
+        }
+    }
+
     // Closing tag
     svg.push_str("</svg>");
     svg.push('\n');
@@ -387,16 +855,334 @@ pub fn convert_file_to_svg<P: AsRef<std::path::Path>>(
     convert_image_to_svg(&image, options)
 }
 
-/// Save SVG content to a file
+/// Write SVG content to any writer, optionally gzip-compressing it (the
+/// `.svgz` convention) instead of writing plain text
+///
+/// # Arguments
+///
+/// * `svg_content` - The SVG content to write
+/// * `writer` - Destination to write to (a file, stdout, ...)
+/// * `compress` - Gzip-compress the output when `true`
+pub fn write_svg<W: std::io::Write>(
+    svg_content: &str,
+    writer: W,
+    compress: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+
+    if compress {
+        let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+        encoder.write_all(svg_content.as_bytes())?;
+        encoder.finish()?;
+    } else {
+        let mut writer = writer;
+        writer.write_all(svg_content.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Save SVG content to a file, optionally gzip-compressing it (e.g. for a
+/// `.svgz` output path)
 ///
 /// # Arguments
 ///
 /// * `svg_content` - The SVG content to save
 /// * `output_path` - Path where to save the SVG file
+/// * `compress` - Gzip-compress the output when `true`
 pub fn save_svg_to_file<P: AsRef<std::path::Path>>(
     svg_content: &str,
     output_path: P,
+    compress: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    std::fs::write(output_path, svg_content)?;
-    Ok(())
+    let file = std::fs::File::create(output_path)?;
+    write_svg(svg_content, file, compress)
+}
+
+/// Output format for the final rendered asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The generated SVG text, unmodified
+    Svg,
+    /// A rasterized PNG rendering of the SVG
+    Png,
+    /// A PDF rendering of the SVG
+    Pdf,
+}
+
+impl OutputFormat {
+    /// Infer the output format from a file extension (case-insensitive);
+    /// `None` if the extension isn't recognized
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "svg" | "svgz" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// Render SVG content to a rasterized PNG, at the size encoded in the SVG's
+/// own `width`/`height` (i.e. `ConversionOptions::scale` already applied).
+/// Crispness for scaled-up pixel art comes from the `shape-rendering="crispEdges"`
+/// attribute `create_svg` already emits on the `<svg>` root; there are no raster
+/// `<image>` elements in the output for `image-rendering` to affect.
+pub fn render_svg_to_png(svg_content: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let options = usvg::Options::default();
+    let tree = usvg::Tree::from_str(svg_content, &options)?;
+    let size = tree.size();
+
+    let mut pixmap = tiny_skia::Pixmap::new(size.width().round() as u32, size.height().round() as u32)
+        .ok_or("Failed to allocate render target for the requested SVG size")?;
+
+    resvg::render(&tree, tiny_skia::Transform::identity(), &mut pixmap.as_mut());
+
+    Ok(pixmap.encode_png()?)
+}
+
+/// Render SVG content to PDF, at the size encoded in the SVG's own
+/// `width`/`height`
+pub fn render_svg_to_pdf(svg_content: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let options = usvg::Options::default();
+    let tree = usvg::Tree::from_str(svg_content, &options)?;
+    svg2pdf::to_pdf(
+        &tree,
+        svg2pdf::ConversionOptions::default(),
+        svg2pdf::PageOptions::default(),
+    )
+    .map_err(|e| e.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distinct_rgb_colors(image: &RgbaImage) -> std::collections::HashSet<[u8; 3]> {
+        image
+            .pixels()
+            .map(|p| [p[0], p[1], p[2]])
+            .collect()
+    }
+
+    #[test]
+    fn quantize_colors_respects_max_colors() {
+        // 4 distinct opaque colors, reduced to at most 2.
+        let image = RgbaImage::from_fn(4, 1, |x, _| match x {
+            0 => Rgba([0, 0, 0, 255]),
+            1 => Rgba([250, 0, 0, 255]),
+            2 => Rgba([0, 250, 0, 255]),
+            _ => Rgba([0, 0, 250, 255]),
+        });
+
+        let quantized = quantize_colors(&image, 1, 2);
+        assert!(distinct_rgb_colors(&quantized).len() <= 2);
+    }
+
+    #[test]
+    fn quantize_colors_collapses_known_pair_to_their_average() {
+        // Two pixels of a single box (max_colors = 1) must collapse to the
+        // weighted per-channel average of every color in the histogram.
+        let image = RgbaImage::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([10, 0, 0, 255])
+            }
+        });
+
+        let quantized = quantize_colors(&image, 1, 1);
+        let colors = distinct_rgb_colors(&quantized);
+        assert_eq!(colors, std::collections::HashSet::from([[5, 0, 0]]));
+    }
+
+    #[test]
+    fn quantize_colors_is_a_no_op_when_already_within_budget() {
+        let image = RgbaImage::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([10, 0, 0, 255])
+            }
+        });
+
+        let quantized = quantize_colors(&image, 1, 2);
+        assert_eq!(*quantized.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*quantized.get_pixel(1, 0), Rgba([10, 0, 0, 255]));
+    }
+
+    #[test]
+    fn create_svg_defaults_to_intrinsic_size_without_viewbox() {
+        let options = ConversionOptions::default();
+        let svg = create_svg(&[], 10, 20, &options);
+
+        assert!(svg.contains(r#"width="10" height="20""#));
+        assert!(!svg.contains("viewBox"));
+    }
+
+    #[test]
+    fn create_svg_width_only_scales_height_proportionally() {
+        // intrinsic 10x20 at scale 1, forced to width 5 -> height halves to 10.
+        let options = ConversionOptions {
+            output_width: Some(5),
+            ..Default::default()
+        };
+        let svg = create_svg(&[], 10, 20, &options);
+
+        assert!(svg.contains(r#"width="5" height="10""#));
+        assert!(svg.contains(r#"viewBox="0 0 10 20""#));
+    }
+
+    #[test]
+    fn create_svg_height_only_scales_width_proportionally() {
+        // intrinsic 10x20 at scale 1, forced to height 40 -> width doubles to 20.
+        let options = ConversionOptions {
+            output_height: Some(40),
+            ..Default::default()
+        };
+        let svg = create_svg(&[], 10, 20, &options);
+
+        assert!(svg.contains(r#"width="20" height="40""#));
+        assert!(svg.contains(r#"viewBox="0 0 10 20""#));
+    }
+
+    #[test]
+    fn create_svg_both_dimensions_given_are_used_verbatim() {
+        let options = ConversionOptions {
+            output_width: Some(7),
+            output_height: Some(3),
+            ..Default::default()
+        };
+        let svg = create_svg(&[], 10, 20, &options);
+
+        assert!(svg.contains(r#"width="7" height="3""#));
+        assert!(svg.contains(r#"viewBox="0 0 10 20""#));
+    }
+
+    #[test]
+    fn create_svg_explicit_viewbox_without_output_dims() {
+        let options = ConversionOptions {
+            viewbox: true,
+            ..Default::default()
+        };
+        let svg = create_svg(&[], 10, 20, &options);
+
+        assert!(svg.contains(r#"width="10" height="20""#));
+        assert!(svg.contains(r#"viewBox="0 0 10 20""#));
+    }
+
+    #[test]
+    fn grouped_paths_collapses_same_color_rectangles_into_one_path() {
+        let red = Color::new(255, 0, 0, 255);
+        let blue = Color::new(0, 0, 255, 255);
+        let rectangles = vec![
+            Rectangle::new(0, 0, 2, 3, red),
+            Rectangle::new(2, 0, 1, 1, red),
+            Rectangle::new(0, 3, 4, 4, blue),
+        ];
+
+        let paths = grouped_paths(&rectangles, 1);
+
+        assert_eq!(paths.len(), 2, "one <path> per distinct color");
+        assert!(paths[0].starts_with(r##"<path fill="#FF0000" "##));
+        assert!(
+            paths[0].contains("M0 0h2v3h-2z") && paths[0].contains("M2 0h1v1h-1z"),
+            "both red rectangles' move/line commands should appear in the red path"
+        );
+        assert!(paths[1].starts_with(r##"<path fill="#0000FF" "##));
+        assert!(paths[1].contains("M0 3h4v4h-4z"));
+    }
+
+    #[test]
+    fn grouped_paths_scales_coordinates_and_applies_opacity() {
+        let translucent = Color::new(0, 255, 0, 128);
+        let rectangles = vec![Rectangle::new(1, 2, 3, 4, translucent)];
+
+        let paths = grouped_paths(&rectangles, 2);
+
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].contains("M2 4h6v8h-6z"));
+        assert!(paths[0].contains(&format!("opacity=\"{:.3}\"", translucent.opacity())));
+    }
+
+    #[test]
+    fn largest_rectangle_in_histogram_picks_max_area() {
+        // Widest run of height >= 1 beats either taller-but-narrower outer bar:
+        // 3 * 1 = 3 > 1 * 2 = 2.
+        let heights = [2, 1, 2];
+        assert_eq!(largest_rectangle_in_histogram(&heights), Some((0, 3, 1)));
+    }
+
+    #[test]
+    fn largest_rectangle_in_histogram_empty_when_all_zero() {
+        assert_eq!(largest_rectangle_in_histogram(&[0, 0, 0]), None);
+    }
+
+    fn l_shaped_image() -> RgbaImage {
+        // 3x3 opaque red square with the bottom-right 1x2 notch cut out:
+        //   R R R
+        //   R R .
+        //   R R .
+        RgbaImage::from_fn(3, 3, |x, y| {
+            if x == 2 && y > 0 {
+                Rgba([0, 0, 0, 0])
+            } else {
+                Rgba([255, 0, 0, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn extract_rectangles_optimal_decomposes_l_shape_into_two_rectangles() {
+        let mut processor = ImageProcessor::new(DynamicImage::ImageRgba8(l_shaped_image()), 1);
+        let rectangles = processor.extract_rectangles_optimal();
+
+        assert_eq!(rectangles.len(), 2);
+        let total_area: u64 = rectangles.iter().map(Rectangle::area).sum();
+        assert_eq!(total_area, 7);
+        assert_eq!(
+            rectangles.iter().map(Rectangle::area).max(),
+            Some(6),
+            "the 2x3 block should be picked before the leftover single cell"
+        );
+    }
+
+    #[test]
+    fn extract_rectangles_tiled_fuses_a_color_run_across_a_band_boundary() {
+        // A single 4x8 opaque red block split into 2 bands of 4 rows each:
+        // each band extracts as one 4x4 rectangle, and fusing must join them
+        // back into one 4x8 rectangle, matching the untiled result.
+        let image = RgbaImage::from_pixel(4, 8, Rgba([255, 0, 0, 255]));
+
+        let untiled = extract_rectangles_tiled(&image, 1, ExtractionQuality::Fast, 1);
+        assert_eq!(untiled.len(), 1);
+        assert_eq!(untiled[0].area(), 32);
+
+        let tiled = extract_rectangles_tiled(&image, 1, ExtractionQuality::Fast, 2);
+        assert_eq!(
+            tiled.len(),
+            1,
+            "same-color run spanning the band boundary should fuse into a single rectangle"
+        );
+        assert_eq!(tiled[0].area(), 32);
+    }
+
+    #[test]
+    fn extract_rectangles_tiled_checkerboard_matches_untiled_count() {
+        // Adjacent cells always differ in color, so no fusion is possible
+        // either way; tiling must not change the total rectangle count.
+        let image = RgbaImage::from_fn(8, 8, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 255])
+            }
+        });
+
+        let untiled = extract_rectangles_tiled(&image, 1, ExtractionQuality::Fast, 1);
+        let tiled = extract_rectangles_tiled(&image, 1, ExtractionQuality::Fast, 4);
+
+        assert_eq!(untiled.len(), tiled.len());
+        assert_eq!(untiled.len(), 64);
+    }
 }
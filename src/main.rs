@@ -1,16 +1,95 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use pix2svg::{convert_file_to_svg, save_svg_to_file, ConversionOptions};
-use std::path::PathBuf;
+use clap::ValueEnum;
+use pix2svg::{
+    convert_image_to_svg, render_svg_to_pdf, render_svg_to_png, save_svg_to_file, write_svg,
+    Color, ConversionOptions, ExtractionQuality, OutputFormat, OutputStyle,
+};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Parse a `RRGGBB` or `RRGGBBAA` hex string into a [`Color`]
+fn parse_hex_color(s: &str) -> Result<Color, String> {
+    let hex = s.trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Expected a RRGGBB or RRGGBBAA hex color, got {:?}", s));
+    }
+    let bytes = match hex.len() {
+        6 => [
+            u8::from_str_radix(&hex[0..2], 16).map_err(|e| e.to_string())?,
+            u8::from_str_radix(&hex[2..4], 16).map_err(|e| e.to_string())?,
+            u8::from_str_radix(&hex[4..6], 16).map_err(|e| e.to_string())?,
+            255,
+        ],
+        8 => [
+            u8::from_str_radix(&hex[0..2], 16).map_err(|e| e.to_string())?,
+            u8::from_str_radix(&hex[2..4], 16).map_err(|e| e.to_string())?,
+            u8::from_str_radix(&hex[4..6], 16).map_err(|e| e.to_string())?,
+            u8::from_str_radix(&hex[6..8], 16).map_err(|e| e.to_string())?,
+        ],
+        _ => return Err(format!("Expected a RRGGBB or RRGGBBAA hex color, got {:?}", s)),
+    };
+
+    Ok(Color::new(bytes[0], bytes[1], bytes[2], bytes[3]))
+}
+
+/// How rectangles are encoded in the generated SVG
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum CliOutputStyle {
+    /// One `<rect>` element per rectangle
+    #[default]
+    Rects,
+    /// Rectangles grouped by color into a single `<path>` per color
+    Paths,
+}
+
+impl From<CliOutputStyle> for OutputStyle {
+    fn from(style: CliOutputStyle) -> Self {
+        match style {
+            CliOutputStyle::Rects => OutputStyle::Rects,
+            CliOutputStyle::Paths => OutputStyle::Paths,
+        }
+    }
+}
+
+/// Rectangle extraction strategy
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum CliExtractionQuality {
+    /// Greedy max-width-then-height extraction (fast, more rectangles)
+    #[default]
+    Fast,
+    /// Global maximal-rectangle search (slower, fewer and larger rectangles)
+    Best,
+}
+
+impl From<CliExtractionQuality> for ExtractionQuality {
+    fn from(quality: CliExtractionQuality) -> Self {
+        match quality {
+            CliExtractionQuality::Fast => ExtractionQuality::Fast,
+            CliExtractionQuality::Best => ExtractionQuality::Best,
+        }
+    }
+}
+
+/// Output format, or `Auto` to infer it from the output file extension
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CliOutputFormat {
+    /// Infer from the output file extension, falling back to SVG
+    #[default]
+    Auto,
+    Svg,
+    Png,
+    Pdf,
+}
 
 /// Convert pixel art images to optimized SVG format
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input image file path
+    /// Input image file path, or `-` to read from stdin
     input: PathBuf,
 
-    /// Output SVG file path
+    /// Output file path (its extension picks SVG, SVGZ, PNG or PDF), or stdout when omitted
     #[arg(short, long)]
     output: Option<PathBuf>,
 
@@ -26,17 +105,74 @@ struct Args {
     #[arg(long)]
     no_crisp_edges: bool,
 
+    /// Reduce the image to at most this many colors before extraction (median-cut)
+    #[arg(long)]
+    max_colors: Option<usize>,
+
+    /// How rectangles are encoded in the generated SVG
+    #[arg(long, value_enum, default_value_t = CliOutputStyle::Rects)]
+    output_style: CliOutputStyle,
+
+    /// Rectangle extraction strategy: fast (greedy) or best (fewer, larger rectangles)
+    #[arg(long, value_enum, default_value_t = CliExtractionQuality::Fast)]
+    quality: CliExtractionQuality,
+
+    /// Gzip-compress the SVG output (implied by a `.svgz` output path)
+    #[arg(long)]
+    compress: bool,
+
+    /// Output format; inferred from the output extension when omitted
+    #[arg(long, value_enum, default_value_t = CliOutputFormat::Auto)]
+    format: CliOutputFormat,
+
+    /// Split the image into this many horizontal bands and extract them in parallel
+    #[arg(long)]
+    parallel_tiles: Option<usize>,
+
+    /// Solid background color (RRGGBB or RRGGBBAA hex) drawn behind the art
+    #[arg(long, value_parser = parse_hex_color)]
+    background: Option<Color>,
+
+    /// Emit a viewBox so the SVG scales responsively independent of its width/height
+    #[arg(long)]
+    view_box: bool,
+
+    /// Explicit output width, overriding the scale-derived size
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Explicit output height, overriding the scale-derived size
+    #[arg(long)]
+    height: Option<u32>,
+
     /// Enable verbose output
     #[arg(short, long)]
     verbose: bool,
 }
 
-fn process_image(args: &Args) -> Result<()> {
+/// `true` when the input path is the `-` convention for "read from stdin"
+fn reads_from_stdin(input: &Path) -> bool {
+    input.as_os_str() == "-"
+}
+
+fn load_image(args: &Args) -> Result<image::DynamicImage> {
+    if reads_from_stdin(&args.input) {
+        if args.verbose {
+            eprintln!("Loading image: <stdin>");
+        }
+
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .context("Failed to read image from stdin")?;
+
+        return image::load_from_memory(&bytes).context("Failed to decode image from stdin");
+    }
+
     if args.verbose {
         eprintln!("Loading image: {:?}", args.input);
     }
 
-    // Validate input file exists
     if !args.input.exists() {
         anyhow::bail!("Input file does not exist: {:?}", args.input);
     }
@@ -45,12 +181,43 @@ fn process_image(args: &Args) -> Result<()> {
         anyhow::bail!("Input path is not a file: {:?}", args.input);
     }
 
+    image::open(&args.input).with_context(|| format!("Failed to open image: {:?}", args.input))
+}
+
+/// Resolve the effective output format: an explicit `--format` wins, otherwise
+/// infer it from the output file extension, falling back to SVG (e.g. for stdout)
+fn resolve_format(args: &Args) -> OutputFormat {
+    match args.format {
+        CliOutputFormat::Svg => OutputFormat::Svg,
+        CliOutputFormat::Png => OutputFormat::Png,
+        CliOutputFormat::Pdf => OutputFormat::Pdf,
+        CliOutputFormat::Auto => args
+            .output
+            .as_ref()
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+            .and_then(OutputFormat::from_extension)
+            .unwrap_or(OutputFormat::Svg),
+    }
+}
+
+fn process_image(args: &Args) -> Result<()> {
+    let image = load_image(args)?;
+
     // Configure conversion options
     let options = ConversionOptions {
         scale: args.scale,
         alpha_threshold: args.alpha_threshold,
         skip_transparent: true,
         crisp_edges: !args.no_crisp_edges,
+        max_colors: args.max_colors,
+        output: args.output_style.into(),
+        quality: args.quality.into(),
+        parallel_tiles: args.parallel_tiles,
+        background: args.background,
+        viewbox: args.view_box,
+        output_width: args.width,
+        output_height: args.height,
     };
 
     if args.verbose {
@@ -58,10 +225,25 @@ fn process_image(args: &Args) -> Result<()> {
         eprintln!("  Scale: {}x", options.scale);
         eprintln!("  Alpha threshold: {}", options.alpha_threshold);
         eprintln!("  Crisp edges: {}", options.crisp_edges);
+        if let Some(max_colors) = options.max_colors {
+            eprintln!("  Max colors: {}", max_colors);
+        }
+        if let Some(tiles) = options.parallel_tiles {
+            eprintln!("  Parallel tiles: {}", tiles);
+        }
+        if let Some(background) = options.background {
+            eprintln!("  Background: #{}", background.to_hex());
+        }
+        if options.output_width.is_some() || options.output_height.is_some() {
+            eprintln!(
+                "  Output size: {:?}x{:?}",
+                options.output_width, options.output_height
+            );
+        }
     }
 
     // Convert image to SVG
-    let result = convert_file_to_svg(&args.input, options)
+    let result = convert_image_to_svg(&image, options)
         .map_err(|e| anyhow::Error::msg(e.to_string()))
         .with_context(|| format!("Failed to convert image: {:?}", args.input))?;
 
@@ -75,22 +257,73 @@ fn process_image(args: &Args) -> Result<()> {
         eprintln!("  SVG size: {} bytes", result.svg_size_bytes());
     }
 
-    // Determine output path
-    let output_path = args.output.clone().unwrap_or_else(|| {
-        let mut path = args.input.clone();
-        path.set_extension("svg");
-        path
-    });
+    match resolve_format(args) {
+        OutputFormat::Svg => {
+            let compress = args.compress
+                || args
+                    .output
+                    .as_ref()
+                    .and_then(|path| path.extension())
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("svgz"));
 
-    // Save SVG file
-    save_svg_to_file(&result.svg_content, &output_path)
-        .map_err(|e| anyhow::Error::msg(e.to_string()))
-        .with_context(|| format!("Failed to save SVG file: {:?}", output_path))?;
+            match &args.output {
+                Some(output_path) => {
+                    save_svg_to_file(&result.svg_content, output_path, compress)
+                        .map_err(|e| anyhow::Error::msg(e.to_string()))
+                        .with_context(|| format!("Failed to save SVG file: {:?}", output_path))?;
 
-    if args.verbose {
-        eprintln!("SVG file saved: {:?}", output_path);
-    } else {
-        println!("Successfully converted to: {:?}", output_path);
+                    if args.verbose {
+                        eprintln!("SVG file saved: {:?}", output_path);
+                    } else {
+                        println!("Successfully converted to: {:?}", output_path);
+                    }
+                }
+                None => {
+                    write_svg(&result.svg_content, std::io::stdout().lock(), compress)
+                        .map_err(|e| anyhow::Error::msg(e.to_string()))
+                        .context("Failed to write SVG to stdout")?;
+                }
+            }
+        }
+        OutputFormat::Png => {
+            let bytes = render_svg_to_png(&result.svg_content)
+                .map_err(|e| anyhow::Error::msg(e.to_string()))
+                .context("Failed to render PNG")?;
+            write_rendered_output(args, &bytes)?;
+        }
+        OutputFormat::Pdf => {
+            let bytes = render_svg_to_pdf(&result.svg_content)
+                .map_err(|e| anyhow::Error::msg(e.to_string()))
+                .context("Failed to render PDF")?;
+            write_rendered_output(args, &bytes)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write already-rendered (PNG/PDF) bytes to the output path, or stdout when none was given
+fn write_rendered_output(args: &Args, bytes: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    match &args.output {
+        Some(output_path) => {
+            std::fs::write(output_path, bytes)
+                .with_context(|| format!("Failed to save rendered file: {:?}", output_path))?;
+
+            if args.verbose {
+                eprintln!("Rendered file saved: {:?}", output_path);
+            } else {
+                println!("Successfully converted to: {:?}", output_path);
+            }
+        }
+        None => {
+            std::io::stdout()
+                .lock()
+                .write_all(bytes)
+                .context("Failed to write rendered output to stdout")?;
+        }
     }
 
     Ok(())